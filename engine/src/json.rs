@@ -1,6 +1,7 @@
 use crate::prelude::{BTreeMap, String, Vec};
 
 use crate::errors;
+use borsh::{BorshDeserialize, BorshSerialize};
 use core::convert::From;
 use rjson::{Array, Null, Object, Value};
 
@@ -23,10 +24,14 @@ pub enum JsonError {
     InvalidU8,
     InvalidU64,
     InvalidU128,
+    InvalidI64,
+    InvalidI128,
     InvalidBool,
     InvalidString,
     InvalidArray,
     ExpectedStringGotNumber,
+    InvalidBinaryEncoding,
+    NegativeValue,
     OutOfRange(JsonOutOfRangeError),
 }
 
@@ -75,6 +80,25 @@ impl JsonValue {
         }
     }
 
+    #[allow(dead_code)]
+    pub fn i64(&self, key: &str) -> Result<i64, JsonError> {
+        match self {
+            JsonValue::Object(o) => match o.get(key).ok_or(JsonError::MissingValue)? {
+                JsonValue::I64(n) => Ok(*n),
+                _ => Err(JsonError::InvalidI64),
+            },
+            _ => Err(JsonError::NotJsonType),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn i128(&self, key: &str) -> Result<i128, JsonError> {
+        match self {
+            JsonValue::Object(o) => o.get(key).ok_or(JsonError::MissingValue)?.try_into(),
+            _ => Err(JsonError::NotJsonType),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn bool(&self, key: &str) -> Result<bool, JsonError> {
         match self {
@@ -101,6 +125,63 @@ impl JsonValue {
     }
 }
 
+/// One step of a `JsonValue::get_path` query: either an object key or an
+/// array index.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+impl JsonValue {
+    /// Descends through nested objects and arrays following `path`.
+    #[allow(dead_code)]
+    pub fn get_path(&self, path: &[PathSegment]) -> Result<&JsonValue, JsonError> {
+        let mut current = self;
+        for segment in path {
+            current = match (current, segment) {
+                (JsonValue::Object(o), PathSegment::Key(key)) => {
+                    o.get(*key).ok_or(JsonError::MissingValue)?
+                }
+                (JsonValue::Array(arr), PathSegment::Index(i)) => {
+                    arr.get(*i).ok_or(JsonError::MissingValue)?
+                }
+                _ => return Err(JsonError::MissingValue),
+            };
+        }
+        Ok(current)
+    }
+
+    #[allow(dead_code)]
+    pub fn string_at(&self, path: &[PathSegment]) -> Result<String, JsonError> {
+        match self.get_path(path)? {
+            JsonValue::String(s) => Ok(s.into()),
+            _ => Err(JsonError::InvalidString),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn u64_at(&self, path: &[PathSegment]) -> Result<u64, JsonError> {
+        match self.get_path(path)? {
+            JsonValue::U64(n) => Ok(*n),
+            _ => Err(JsonError::InvalidU64),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn u128_at(&self, path: &[PathSegment]) -> Result<u128, JsonError> {
+        self.get_path(path)?.try_into()
+    }
+
+    #[allow(dead_code)]
+    pub fn bool_at(&self, path: &[PathSegment]) -> Result<bool, JsonError> {
+        match self.get_path(path)? {
+            JsonValue::Bool(n) => Ok(*n),
+            _ => Err(JsonError::InvalidBool),
+        }
+    }
+}
+
 impl AsRef<[u8]> for JsonError {
     fn as_ref(&self) -> &[u8] {
         match self {
@@ -112,7 +193,11 @@ impl AsRef<[u8]> for JsonError {
             Self::InvalidBool => errors::ERR_FAILED_PARSE_BOOL,
             Self::InvalidString => errors::ERR_FAILED_PARSE_STRING,
             Self::InvalidArray => errors::ERR_FAILED_PARSE_ARRAY,
+            Self::InvalidI64 => errors::ERR_FAILED_PARSE_I64,
+            Self::InvalidI128 => errors::ERR_FAILED_PARSE_I128,
             Self::ExpectedStringGotNumber => errors::ERR_EXPECTED_STRING_GOT_NUMBER,
+            Self::InvalidBinaryEncoding => errors::ERR_INVALID_JSON_BINARY_ENCODING,
+            Self::NegativeValue => errors::ERR_JSON_NEGATIVE_VALUE,
             Self::OutOfRange(err) => err.as_ref(),
         }
     }
@@ -221,19 +306,334 @@ impl TryFrom<&JsonValue> for u128 {
                 if let Ok(x) = n.parse::<u128>() {
                     Ok(x)
                 } else if n.parse::<i128>().is_ok() {
-                    Err(JsonError::OutOfRange(JsonOutOfRangeError::OutOfRangeU128))
+                    Err(JsonError::NegativeValue)
                 } else {
                     Err(JsonError::InvalidU128)
                 }
             }
+            // Widening a `U64` to `u128` is always lossless, so numeric
+            // amounts don't have to be quoted as strings to be accepted here.
+            JsonValue::U64(n) => Ok(*n as u128),
             JsonValue::F64(_) => Err(JsonError::ExpectedStringGotNumber),
+            JsonValue::I64(n) if *n < 0 => Err(JsonError::NegativeValue),
             JsonValue::I64(_) => Err(JsonError::ExpectedStringGotNumber),
-            JsonValue::U64(_) => Err(JsonError::ExpectedStringGotNumber),
             _ => Err(JsonError::InvalidU128),
         }
     }
 }
 
+impl TryFrom<&JsonValue> for i128 {
+    type Error = JsonError;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::String(n) => n.parse::<i128>().map_err(|_| JsonError::InvalidI128),
+            JsonValue::I64(n) => Ok(*n as i128),
+            JsonValue::U64(n) => Ok(*n as i128),
+            JsonValue::F64(_) => Err(JsonError::ExpectedStringGotNumber),
+            _ => Err(JsonError::InvalidI128),
+        }
+    }
+}
+
+/// One-byte discriminants for the binary encoding below; append-only.
+mod binary_tag {
+    pub const NULL: u8 = 0;
+    pub const BOOL: u8 = 1;
+    pub const I64: u8 = 2;
+    pub const U64: u8 = 3;
+    pub const F64: u8 = 4;
+    pub const STRING: u8 = 5;
+    pub const ARRAY: u8 = 6;
+    pub const OBJECT: u8 = 7;
+}
+
+/// Max nesting depth `read_binary` will recurse through.
+const MAX_BINARY_DEPTH: usize = 64;
+
+impl JsonValue {
+    /// Encodes `self` into a compact, self-describing binary format.
+    #[allow(dead_code)]
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.write_binary(&mut buf);
+        buf
+    }
+
+    fn write_binary(&self, buf: &mut Vec<u8>) {
+        match self {
+            JsonValue::Null => buf.push(binary_tag::NULL),
+            JsonValue::Bool(v) => {
+                buf.push(binary_tag::BOOL);
+                buf.push(*v as u8);
+            }
+            JsonValue::I64(v) => {
+                buf.push(binary_tag::I64);
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+            JsonValue::U64(v) => {
+                buf.push(binary_tag::U64);
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+            JsonValue::F64(v) => {
+                buf.push(binary_tag::F64);
+                let normalized = if *v == 0.0 { 0.0_f64 } else { *v };
+                buf.extend_from_slice(&normalized.to_le_bytes());
+            }
+            JsonValue::String(v) => {
+                buf.push(binary_tag::STRING);
+                buf.extend_from_slice(&(v.len() as u32).to_le_bytes());
+                buf.extend_from_slice(v.as_bytes());
+            }
+            JsonValue::Array(arr) => {
+                buf.push(binary_tag::ARRAY);
+                buf.extend_from_slice(&(arr.len() as u32).to_le_bytes());
+                for item in arr {
+                    item.write_binary(buf);
+                }
+            }
+            JsonValue::Object(kvs) => {
+                buf.push(binary_tag::OBJECT);
+                buf.extend_from_slice(&(kvs.len() as u32).to_le_bytes());
+                for (key, value) in kvs {
+                    buf.extend_from_slice(&(key.len() as u32).to_le_bytes());
+                    buf.extend_from_slice(key.as_bytes());
+                    value.write_binary(buf);
+                }
+            }
+        }
+    }
+
+    /// Decodes a buffer produced by `to_binary`. Every length/count is
+    /// bounds-checked against the remaining input, and any trailing bytes
+    /// after a complete value are rejected.
+    #[allow(dead_code)]
+    pub fn from_binary(data: &[u8]) -> Result<JsonValue, JsonError> {
+        let mut cursor = 0usize;
+        let value = JsonValue::read_binary(data, &mut cursor, 0)?;
+        if cursor != data.len() {
+            return Err(JsonError::InvalidBinaryEncoding);
+        }
+        Ok(value)
+    }
+
+    fn read_binary(data: &[u8], cursor: &mut usize, depth: usize) -> Result<JsonValue, JsonError> {
+        if depth >= MAX_BINARY_DEPTH {
+            return Err(JsonError::InvalidBinaryEncoding);
+        }
+        let tag = Self::take_bytes(data, cursor, 1)?[0];
+        match tag {
+            binary_tag::NULL => Ok(JsonValue::Null),
+            binary_tag::BOOL => {
+                let b = Self::take_bytes(data, cursor, 1)?[0];
+                Ok(JsonValue::Bool(b != 0))
+            }
+            binary_tag::I64 => {
+                let bytes = Self::take_bytes(data, cursor, 8)?;
+                Ok(JsonValue::I64(i64::from_le_bytes(
+                    bytes.try_into().unwrap(),
+                )))
+            }
+            binary_tag::U64 => {
+                let bytes = Self::take_bytes(data, cursor, 8)?;
+                Ok(JsonValue::U64(u64::from_le_bytes(
+                    bytes.try_into().unwrap(),
+                )))
+            }
+            binary_tag::F64 => {
+                let bytes = Self::take_bytes(data, cursor, 8)?;
+                Ok(JsonValue::F64(f64::from_le_bytes(
+                    bytes.try_into().unwrap(),
+                )))
+            }
+            binary_tag::STRING => {
+                let s = Self::read_binary_string(data, cursor)?;
+                Ok(JsonValue::String(s))
+            }
+            binary_tag::ARRAY => {
+                let len = Self::read_len(data, cursor)?;
+                if len > data.len() - *cursor {
+                    return Err(JsonError::InvalidBinaryEncoding);
+                }
+                let mut arr = Vec::with_capacity(len);
+                for _ in 0..len {
+                    arr.push(Self::read_binary(data, cursor, depth + 1)?);
+                }
+                Ok(JsonValue::Array(arr))
+            }
+            binary_tag::OBJECT => {
+                let len = Self::read_len(data, cursor)?;
+                let mut kvs = BTreeMap::new();
+                for _ in 0..len {
+                    let key = Self::read_binary_string(data, cursor)?;
+                    let value = Self::read_binary(data, cursor, depth + 1)?;
+                    kvs.insert(key, value);
+                }
+                Ok(JsonValue::Object(kvs))
+            }
+            _ => Err(JsonError::InvalidBinaryEncoding),
+        }
+    }
+
+    fn read_len(data: &[u8], cursor: &mut usize) -> Result<usize, JsonError> {
+        let bytes = Self::take_bytes(data, cursor, 4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+    }
+
+    fn read_binary_string(data: &[u8], cursor: &mut usize) -> Result<String, JsonError> {
+        let len = Self::read_len(data, cursor)?;
+        let bytes = Self::take_bytes(data, cursor, len)?;
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| JsonError::InvalidBinaryEncoding)
+    }
+
+    fn take_bytes<'a>(
+        data: &'a [u8],
+        cursor: &mut usize,
+        len: usize,
+    ) -> Result<&'a [u8], JsonError> {
+        let end = cursor
+            .checked_add(len)
+            .ok_or(JsonError::InvalidBinaryEncoding)?;
+        let bytes = data
+            .get(*cursor..end)
+            .ok_or(JsonError::InvalidBinaryEncoding)?;
+        *cursor = end;
+        Ok(bytes)
+    }
+}
+
+impl BorshSerialize for JsonValue {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        BorshSerialize::serialize(&self.to_binary(), writer)
+    }
+}
+
+impl BorshDeserialize for JsonValue {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        let bytes: Vec<u8> = BorshDeserialize::deserialize(buf)?;
+        JsonValue::from_binary(&bytes).map_err(|_| {
+            borsh::maybestd::io::Error::new(
+                borsh::maybestd::io::ErrorKind::InvalidData,
+                "invalid JsonValue binary encoding",
+            )
+        })
+    }
+}
+
+const HEX_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+/// Writes `s` escaped per RFC 8259 (without the surrounding quotes) into `buf`.
+fn write_escaped_str(buf: &mut Vec<u8>, s: &str) {
+    for c in s.chars() {
+        match c {
+            '"' => buf.extend_from_slice(b"\\\""),
+            '\\' => buf.extend_from_slice(b"\\\\"),
+            '\n' => buf.extend_from_slice(b"\\n"),
+            '\r' => buf.extend_from_slice(b"\\r"),
+            '\t' => buf.extend_from_slice(b"\\t"),
+            '\u{08}' => buf.extend_from_slice(b"\\b"),
+            '\u{0C}' => buf.extend_from_slice(b"\\f"),
+            c if (c as u32) < 0x20 => {
+                let b = c as u8;
+                buf.extend_from_slice(b"\\u00");
+                buf.push(HEX_ALPHABET[(b / 16) as usize]);
+                buf.push(HEX_ALPHABET[(b % 16) as usize]);
+            }
+            c => {
+                let mut tmp = [0u8; 4];
+                buf.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+            }
+        }
+    }
+}
+
+impl JsonValue {
+    /// Serializes `self` as JSON into `buf`, escaping strings per RFC 8259.
+    /// Unlike the `Debug`/`Display` impls below, this is guaranteed to
+    /// produce round-trippable output for any string content.
+    #[allow(dead_code)]
+    pub fn write_json(&self, buf: &mut Vec<u8>) {
+        match self {
+            JsonValue::Null => buf.extend_from_slice(b"null"),
+            JsonValue::String(v) => {
+                buf.push(b'"');
+                write_escaped_str(buf, v);
+                buf.push(b'"');
+            }
+            JsonValue::F64(v) => write_display(buf, v),
+            JsonValue::I64(v) => {
+                if *v < 0 {
+                    buf.push(b'-');
+                }
+                write_decimal(buf, v.unsigned_abs() as u128);
+            }
+            JsonValue::U64(v) => write_decimal(buf, *v as u128),
+            JsonValue::Bool(v) => buf.extend_from_slice(if *v { b"true" } else { b"false" }),
+            JsonValue::Array(arr) => {
+                buf.push(b'[');
+                let mut items = arr.iter();
+                if let Some(item) = items.next() {
+                    item.write_json(buf);
+                }
+                for item in items {
+                    buf.push(b',');
+                    item.write_json(buf);
+                }
+                buf.push(b']');
+            }
+            JsonValue::Object(kvs) => {
+                buf.push(b'{');
+                let mut pairs = kvs.iter();
+                if let Some((key, value)) = pairs.next() {
+                    buf.push(b'"');
+                    write_escaped_str(buf, key);
+                    buf.extend_from_slice(b"\":");
+                    value.write_json(buf);
+                }
+                for (key, value) in pairs {
+                    buf.push(b',');
+                    buf.push(b'"');
+                    write_escaped_str(buf, key);
+                    buf.extend_from_slice(b"\":");
+                    value.write_json(buf);
+                }
+                buf.push(b'}');
+            }
+        }
+    }
+}
+
+/// Writes a `Display` value's textual form into `buf` without going through
+/// `Formatter`'s `Debug`-only string escaping.
+fn write_display(buf: &mut Vec<u8>, v: impl core::fmt::Display) {
+    use core::fmt::Write;
+    let mut s = String::new();
+    let _ = write!(s, "{}", v);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Writes the decimal digits of `val` with no allocation beyond the small
+/// fixed-size stack buffer needed to reverse them into place.
+fn write_decimal(buf: &mut Vec<u8>, mut val: u128) {
+    if val == 0 {
+        buf.push(b'0');
+        return;
+    }
+    let mut digits = [0u8; 39]; // u128::MAX has 39 decimal digits
+    let mut i = digits.len();
+    while val > 0 {
+        i -= 1;
+        digits[i] = b'0' + (val % 10) as u8;
+        val /= 10;
+    }
+    buf.extend_from_slice(&digits[i..]);
+}
+
 impl core::fmt::Debug for JsonValue {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
@@ -275,8 +675,484 @@ impl core::fmt::Display for JsonValue {
     }
 }
 
+/// Which kind of container a `JsonBuilder` frame is currently writing into.
+enum JsonBuilderFrame {
+    Object { needs_comma: bool },
+    Array { needs_comma: bool },
+}
+
+/// Incremental JSON writer, modeled on Suricata's `jsonbuilder`.
+pub struct JsonBuilder {
+    buf: Vec<u8>,
+    stack: Vec<JsonBuilderFrame>,
+}
+
+impl JsonBuilder {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Writes a leading comma if the current (innermost) frame already has
+    /// an element, then marks that frame as having one.
+    fn maybe_comma(&mut self) {
+        if let Some(frame) = self.stack.last_mut() {
+            let needs_comma = match frame {
+                JsonBuilderFrame::Object { needs_comma } => needs_comma,
+                JsonBuilderFrame::Array { needs_comma } => needs_comma,
+            };
+            if *needs_comma {
+                self.buf.push(b',');
+            }
+            *needs_comma = true;
+        }
+    }
+
+    fn write_key(&mut self, key: &str) {
+        self.buf.push(b'"');
+        write_escaped_str(&mut self.buf, key);
+        self.buf.push(b'"');
+        self.buf.push(b':');
+    }
+
+    fn write_decimal(&mut self, val: u128) {
+        write_decimal(&mut self.buf, val);
+    }
+
+    #[allow(dead_code)]
+    pub fn start_object(&mut self) -> &mut Self {
+        self.maybe_comma();
+        self.buf.push(b'{');
+        self.stack
+            .push(JsonBuilderFrame::Object { needs_comma: false });
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn start_array(&mut self) -> &mut Self {
+        self.maybe_comma();
+        self.buf.push(b'[');
+        self.stack
+            .push(JsonBuilderFrame::Array { needs_comma: false });
+        self
+    }
+
+    /// Closes the innermost open frame. In test builds, panics if there was
+    /// no open frame, so a stray extra `close()` call is caught immediately
+    /// instead of silently no-op'ing.
+    #[allow(dead_code)]
+    pub fn close(&mut self) -> &mut Self {
+        match self.stack.pop() {
+            Some(JsonBuilderFrame::Object { .. }) => self.buf.push(b'}'),
+            Some(JsonBuilderFrame::Array { .. }) => self.buf.push(b']'),
+            None => {
+                #[cfg(test)]
+                panic!("JsonBuilder::close called with no open frame");
+            }
+        }
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn set_string(&mut self, key: &str, val: &str) -> &mut Self {
+        self.maybe_comma();
+        self.write_key(key);
+        self.buf.push(b'"');
+        write_escaped_str(&mut self.buf, val);
+        self.buf.push(b'"');
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn set_uint(&mut self, key: &str, val: u64) -> &mut Self {
+        self.maybe_comma();
+        self.write_key(key);
+        self.write_decimal(val as u128);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn set_u128_as_string(&mut self, key: &str, val: u128) -> &mut Self {
+        self.maybe_comma();
+        self.write_key(key);
+        self.buf.push(b'"');
+        self.write_decimal(val);
+        self.buf.push(b'"');
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn set_bool(&mut self, key: &str, val: bool) -> &mut Self {
+        self.maybe_comma();
+        self.write_key(key);
+        self.buf
+            .extend_from_slice(if val { b"true" } else { b"false" });
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn append_string(&mut self, val: &str) -> &mut Self {
+        self.maybe_comma();
+        self.buf.push(b'"');
+        write_escaped_str(&mut self.buf, val);
+        self.buf.push(b'"');
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn append_object(&mut self, val: &JsonValue) -> &mut Self {
+        self.maybe_comma();
+        val.write_json(&mut self.buf);
+        self
+    }
+
+    /// In test builds, panics if a frame was left unclosed so mismatched
+    /// `start_object`/`start_array`/`close` calls are caught immediately.
+    #[allow(dead_code)]
+    pub fn into_bytes(self) -> Vec<u8> {
+        #[cfg(test)]
+        assert!(
+            self.stack.is_empty(),
+            "JsonBuilder::into_bytes called with an open frame"
+        );
+        self.buf
+    }
+}
+
+impl Default for JsonBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decodes `data` as UTF-8 into a `Vec<char>` for `rjson`.
+fn decode_utf8(data: &[u8]) -> Option<Vec<char>> {
+    let mut chars = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        let b0 = data[i];
+        let (len, min, mut cp) = match b0 {
+            0x00..=0x7F => (1, 0, b0 as u32),
+            0xC0..=0xDF => (2, 0x80, (b0 & 0x1F) as u32),
+            0xE0..=0xEF => (3, 0x800, (b0 & 0x0F) as u32),
+            0xF0..=0xF7 => (4, 0x10000, (b0 & 0x07) as u32),
+            _ => return None,
+        };
+        if i + len > data.len() {
+            return None;
+        }
+        for &b in &data[i + 1..i + len] {
+            if b & 0xC0 != 0x80 {
+                return None;
+            }
+            cp = (cp << 6) | (b & 0x3F) as u32;
+        }
+        if cp < min || cp > 0x10FFFF || (0xD800..=0xDFFF).contains(&cp) {
+            return None;
+        }
+        chars.push(char::from_u32(cp)?);
+        i += len;
+    }
+    Some(chars)
+}
+
+fn is_high_surrogate(cp: u32) -> bool {
+    (0xD800..=0xDBFF).contains(&cp)
+}
+
+fn is_low_surrogate(cp: u32) -> bool {
+    (0xDC00..=0xDFFF).contains(&cp)
+}
+
+/// Parses the 4 hex digits right after a `\u` marker.
+fn parse_hex4(chars: &[char]) -> Option<u32> {
+    let digits = chars.get(..4)?;
+    let mut cp = 0u32;
+    for c in digits {
+        cp = cp * 16 + c.to_digit(16)?;
+    }
+    Some(cp)
+}
+
+/// `rjson` decodes each `\uXXXX` escape into a `char` independently, which
+/// can't represent a lone UTF-16 surrogate half — so a codepoint outside the
+/// Basic Multilingual Plane (e.g. emoji), encoded as a surrogate pair, would
+/// never reach it as valid input. This collapses such pairs into the single
+/// `char` they denote before parsing, and rejects a lone (unpaired)
+/// surrogate escape outright. Plain `\uXXXX` escapes are left untouched for
+/// `rjson` to handle as before.
+fn splice_surrogate_pairs(chars: Vec<char>) -> Option<Vec<char>> {
+    let mut out = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '\\' {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        if chars.get(i + 1) != Some(&'u') {
+            // Some other escape sequence (`\\`, `\"`, `\n`, ...): always
+            // exactly two source characters, and never starts a `\u`
+            // escape, so just copy it through as a unit.
+            out.push(chars[i]);
+            if let Some(&c) = chars.get(i + 1) {
+                out.push(c);
+                i += 2;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        let high = parse_hex4(&chars[i + 2..])?;
+        if is_high_surrogate(high) {
+            if chars.get(i + 6) == Some(&'\\') && chars.get(i + 7) == Some(&'u') {
+                let low = parse_hex4(&chars[i + 8..])?;
+                if is_low_surrogate(low) {
+                    let cp = 0x10000 + (high - 0xD800) * 0x400 + (low - 0xDC00);
+                    out.push(char::from_u32(cp)?);
+                    i += 12;
+                    continue;
+                }
+            }
+            return None; // lone high surrogate escape
+        }
+        if is_low_surrogate(high) {
+            return None; // lone low surrogate escape
+        }
+        // Plain (non-surrogate) `\uXXXX`: leave for rjson to decode.
+        out.extend_from_slice(&chars[i..i + 6]);
+        i += 6;
+    }
+    Some(out)
+}
+
 pub fn parse_json(data: &[u8]) -> Option<JsonValue> {
-    let data_array: Vec<char> = data.iter().map(|b| *b as char).collect::<Vec<_>>();
+    let data_array = splice_surrogate_pairs(decode_utf8(data)?)?;
     let mut index = 0;
     rjson::parse::<JsonValue, JsonArray, JsonObject, JsonValue>(&*data_array, &mut index)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_builder_array() {
+        let mut b = JsonBuilder::new();
+        b.start_array();
+        b.append_string("a");
+        b.append_string("b\nc");
+        b.close();
+        assert_eq!(b.into_bytes(), br#"["a","b\nc"]"#);
+    }
+
+    #[test]
+    fn test_json_builder_object() {
+        let mut b = JsonBuilder::new();
+        b.start_object();
+        b.set_string("k", "v\"");
+        b.set_uint("n", 42);
+        b.set_bool("f", true);
+        b.close();
+        assert_eq!(b.into_bytes(), br#"{"k":"v\"","n":42,"f":true}"#);
+    }
+
+    #[test]
+    fn test_json_builder_u128_and_append_object() {
+        let mut b = JsonBuilder::new();
+        b.start_object();
+        b.set_u128_as_string("amount", u128::MAX);
+        b.close();
+        assert_eq!(
+            b.into_bytes(),
+            format!("{{\"amount\":\"{}\"}}", u128::MAX).into_bytes()
+        );
+
+        let mut arr = JsonBuilder::new();
+        arr.start_array();
+        arr.append_object(&JsonValue::Null);
+        arr.close();
+        assert_eq!(arr.into_bytes(), b"[null]");
+    }
+
+    #[test]
+    #[should_panic(expected = "JsonBuilder::close called with no open frame")]
+    fn test_json_builder_close_without_open_frame_panics() {
+        let mut b = JsonBuilder::new();
+        b.close();
+    }
+
+    #[test]
+    fn test_decode_utf8_multibyte() {
+        let input = "héllo 世界";
+        assert_eq!(
+            decode_utf8(input.as_bytes()).unwrap(),
+            input.chars().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_decode_utf8_rejects_invalid_continuation() {
+        assert!(decode_utf8(&[0xC0, 0x20]).is_none());
+    }
+
+    #[test]
+    fn test_decode_utf8_rejects_overlong_encoding() {
+        // 0xC0 0x80 is an overlong two-byte encoding of U+0000.
+        assert!(decode_utf8(&[0xC0, 0x80]).is_none());
+    }
+
+    #[test]
+    fn test_splice_surrogate_pairs_combines_escape() {
+        let chars: Vec<char> = "\\uD83D\\uDE00".chars().collect();
+        let spliced = splice_surrogate_pairs(chars).unwrap();
+        assert_eq!(spliced, vec!['\u{1F600}']);
+    }
+
+    #[test]
+    fn test_parse_json_surrogate_pair_escape() {
+        let json = b"{\"emoji\":\"\\uD83D\\uDE00\"}";
+        let value = parse_json(json).unwrap();
+        assert_eq!(value.string("emoji").unwrap(), "\u{1F600}");
+    }
+
+    #[test]
+    fn test_parse_json_rejects_lone_surrogate() {
+        let json = br#"{"bad":"\uD800"}"#;
+        assert!(parse_json(json).is_none());
+    }
+
+    #[test]
+    fn test_splice_surrogate_pairs_leaves_plain_escape_untouched() {
+        let chars: Vec<char> = "\\u0041".chars().collect();
+        assert_eq!(splice_surrogate_pairs(chars.clone()).unwrap(), chars);
+    }
+
+    #[test]
+    fn test_splice_surrogate_pairs_leaves_escaped_backslash_untouched() {
+        // `\\uD800` is an escaped backslash followed by the literal text
+        // "uD800" -- not a `\u` escape -- and must not be mistaken for one.
+        let chars: Vec<char> = "\\\\uD800".chars().collect();
+        assert_eq!(splice_surrogate_pairs(chars.clone()).unwrap(), chars);
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut obj = BTreeMap::new();
+        obj.insert(String::from("a"), JsonValue::U64(7));
+        obj.insert(
+            String::from("b"),
+            JsonValue::Array(vec![
+                JsonValue::Bool(true),
+                JsonValue::String(String::from("x")),
+            ]),
+        );
+        let original = JsonValue::Object(obj);
+        let bytes = original.to_binary();
+        let decoded = JsonValue::from_binary(&bytes).unwrap();
+        assert!(original == decoded);
+    }
+
+    #[test]
+    fn test_binary_decode_rejects_oversized_length_claim() {
+        let mut bytes = vec![binary_tag::ARRAY];
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            JsonValue::from_binary(&bytes),
+            Err(JsonError::InvalidBinaryEncoding)
+        );
+    }
+
+    #[test]
+    fn test_binary_decode_rejects_excessive_nesting() {
+        let mut bytes = Vec::new();
+        for _ in 0..(MAX_BINARY_DEPTH + 10) {
+            bytes.push(binary_tag::ARRAY);
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+        }
+        bytes.push(binary_tag::NULL);
+        assert_eq!(
+            JsonValue::from_binary(&bytes),
+            Err(JsonError::InvalidBinaryEncoding)
+        );
+    }
+
+    #[test]
+    fn test_binary_negative_zero_normalizes() {
+        let bytes_neg = JsonValue::F64(-0.0).to_binary();
+        let bytes_pos = JsonValue::F64(0.0).to_binary();
+        assert_eq!(bytes_neg, bytes_pos);
+    }
+
+    #[test]
+    fn test_i64_and_i128_accessors() {
+        let mut o = BTreeMap::new();
+        o.insert(String::from("a"), JsonValue::I64(-42));
+        o.insert(
+            String::from("b"),
+            JsonValue::String(String::from("-170141183460469231731687303715884105728")),
+        );
+        let v = JsonValue::Object(o);
+        assert_eq!(v.i64("a").unwrap(), -42);
+        assert_eq!(v.i128("b").unwrap(), i128::MIN);
+    }
+
+    #[test]
+    fn test_u128_try_from_widens_u64_losslessly() {
+        let value = JsonValue::U64(u64::MAX);
+        let n: u128 = (&value).try_into().unwrap();
+        assert_eq!(n, u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_u128_try_from_rejects_negative_string() {
+        let value = JsonValue::String(String::from("-1"));
+        assert_eq!(
+            TryInto::<u128>::try_into(&value),
+            Err(JsonError::NegativeValue)
+        );
+    }
+
+    #[test]
+    fn test_u128_try_from_rejects_negative_i64() {
+        let value = JsonValue::I64(-1);
+        assert_eq!(
+            TryInto::<u128>::try_into(&value),
+            Err(JsonError::NegativeValue)
+        );
+    }
+
+    #[test]
+    fn test_i128_try_from_widens_i64_and_u64() {
+        let neg: i128 = (&JsonValue::I64(-7)).try_into().unwrap();
+        assert_eq!(neg, -7);
+        let pos: i128 = (&JsonValue::U64(7)).try_into().unwrap();
+        assert_eq!(pos, 7);
+    }
+
+    #[test]
+    fn test_i128_try_from_rejects_float() {
+        assert_eq!(
+            TryInto::<i128>::try_into(&JsonValue::F64(1.0)),
+            Err(JsonError::ExpectedStringGotNumber)
+        );
+    }
+
+    #[test]
+    fn test_write_json_integers() {
+        let mut buf = Vec::new();
+        JsonValue::I64(-42).write_json(&mut buf);
+        assert_eq!(buf, b"-42");
+
+        let mut buf = Vec::new();
+        JsonValue::I64(0).write_json(&mut buf);
+        assert_eq!(buf, b"0");
+
+        let mut buf = Vec::new();
+        JsonValue::U64(42).write_json(&mut buf);
+        assert_eq!(buf, b"42");
+    }
+}